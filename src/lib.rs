@@ -0,0 +1,389 @@
+//! Walks an Obsidian vault and writes out a filtered, postprocessed copy of it.
+
+pub mod postprocessors;
+pub mod preprocessors;
+mod vault_ignore;
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use pulldown_cmark::{Event, Parser};
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use snafu::{ResultExt, Snafu};
+
+use preprocessors::Preprocessor;
+use vault_ignore::{is_always_skipped_dir, split_include_glob, IgnoreStack};
+
+/// A sequence of [`pulldown_cmark`] events making up a note's parsed body.
+pub type MarkdownEvents<'e> = Vec<Event<'e>>;
+
+/// A hook run against a note's parsed body after it has been tokenized.
+pub type Postprocessor = dyn Fn(&mut Context, &mut MarkdownEvents<'_>) -> PostprocessorResult + Send + Sync;
+
+/// A hook tested against a note's frontmatter to decide whether it may be pulled into
+/// another note via embed/transclusion resolution (`![[other note]]`).
+pub type EmbedFilter = dyn Fn(&Value) -> bool + Send + Sync;
+
+/// What a [`Postprocessor`] or [`Preprocessor`] decided should happen to a note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostprocessorResult {
+    /// Continue running the remaining postprocessors (or parse the body, for a preprocessor).
+    Continue,
+    /// Stop running postprocessors and don't write this note to the destination.
+    StopAndSkipNote,
+    /// Stop running postprocessors but still write the note out as-is.
+    Stop,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ExportError {
+    #[snafu(display("failed to read from {}", path.display()))]
+    Read { path: PathBuf, source: io::Error },
+    #[snafu(display("failed to write to {}", path.display()))]
+    Write { path: PathBuf, source: io::Error },
+}
+
+/// State threaded through a single note's postprocessors as it's exported.
+pub struct Context {
+    file: PathBuf,
+    pub destination: PathBuf,
+    pub frontmatter: Mapping,
+}
+
+impl Context {
+    fn new(file: PathBuf, destination: PathBuf, frontmatter: Mapping) -> Context {
+        Context {
+            file,
+            destination,
+            frontmatter,
+        }
+    }
+
+    /// The note's path within the source vault.
+    pub fn current_file(&self) -> &PathBuf {
+        &self.file
+    }
+}
+
+/// Options controlling how a vault is walked and which notes are exported.
+#[derive(Default)]
+pub struct WalkOptions<'a> {
+    /// Only export notes whose vault-relative path matches one of these globs. An empty list
+    /// means every note (that isn't otherwise ignored) is included.
+    pub include_globs: Vec<String>,
+    /// Run against a note's path and frontmatter before its body is tokenized, so a note
+    /// that's going to be skipped never pays for parsing.
+    pub preprocessors: Vec<&'a Preprocessor>,
+    pub postprocessors: Vec<&'a Postprocessor>,
+    /// Gate which notes may be pulled into another note's output via `![[embed]]`
+    /// resolution, independently of `include_globs`/`preprocessors`/`postprocessors`, which
+    /// only decide whether a note is written out as its own export-output file.
+    pub embed_filters: Vec<&'a EmbedFilter>,
+}
+
+impl<'a> WalkOptions<'a> {
+    pub fn new() -> WalkOptions<'a> {
+        WalkOptions::default()
+    }
+
+    pub fn with_include_glob(mut self, glob: impl Into<String>) -> WalkOptions<'a> {
+        self.include_globs.push(glob.into());
+        self
+    }
+}
+
+/// Walks `vault` and writes every included note out under `destination`.
+pub fn export_vault(vault: &Path, destination: &Path, options: &WalkOptions<'_>) -> Result<(), ExportError> {
+    let mut ignores = IgnoreStack::new();
+    walk_dir(vault, vault, destination, &mut ignores, options)
+}
+
+fn walk_dir(
+    dir: &Path,
+    vault: &Path,
+    destination: &Path,
+    ignores: &mut IgnoreStack,
+    options: &WalkOptions<'_>,
+) -> Result<(), ExportError> {
+    let pushed = ignores.push_dir(dir).context(ReadSnafu { path: dir.to_path_buf() })?;
+
+    let entries = fs::read_dir(dir).context(ReadSnafu { path: dir.to_path_buf() })?;
+    for entry in entries {
+        let entry = entry.context(ReadSnafu { path: dir.to_path_buf() })?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if entry.file_type().context(ReadSnafu { path: path.clone() })?.is_dir() {
+            if is_always_skipped_dir(&name) {
+                continue;
+            }
+            // Don't test `is_ignored` on the directory itself before descending: a
+            // dir-only exclusion from a shallower ignore file can be negated by a
+            // `.export-ignore` inside this very directory, which hasn't been pushed onto
+            // the stack yet. Exclusion is only decided once we reach an actual file,
+            // by which point every ancestor directory's ignore file is loaded.
+            if !dir_could_contain_included_notes(&path, vault, options) {
+                continue;
+            }
+            walk_dir(&path, vault, destination, ignores, options)?;
+        } else {
+            if ignores.is_ignored(&path, false) {
+                continue;
+            }
+            if !path_is_included(&path, vault, options) {
+                continue;
+            }
+            export_note(&path, vault, destination, options)?;
+        }
+    }
+
+    if pushed {
+        ignores.pop();
+    }
+    Ok(())
+}
+
+/// A performance-only pruning check, independent of ignore-file exclusion: false when no include glob could possibly match anything under `dir`.
+fn dir_could_contain_included_notes(dir: &Path, vault: &Path, options: &WalkOptions<'_>) -> bool {
+    if options.include_globs.is_empty() {
+        return true;
+    }
+    let relative = dir.strip_prefix(vault).unwrap_or(dir);
+    options.include_globs.iter().any(|pattern| {
+        let (base, _) = split_include_glob(pattern);
+        relative.starts_with(&base) || base.starts_with(relative)
+    })
+}
+
+fn path_is_included(path: &Path, vault: &Path, options: &WalkOptions<'_>) -> bool {
+    if options.include_globs.is_empty() {
+        return true;
+    }
+    let relative = path.strip_prefix(vault).unwrap_or(path);
+    options.include_globs.iter().any(|pattern| {
+        let (base, relative_pattern) = split_include_glob(pattern);
+        match relative.strip_prefix(&base) {
+            Ok(rest) => vault_ignore::glob_match(&relative_pattern, &rest.to_string_lossy()),
+            Err(_) => false,
+        }
+    })
+}
+
+fn export_note(path: &Path, vault: &Path, destination: &Path, options: &WalkOptions<'_>) -> Result<(), ExportError> {
+    let content = fs::read_to_string(path).context(ReadSnafu { path: path.to_path_buf() })?;
+    let frontmatter = read_frontmatter(&content);
+    let frontmatter = Value::Mapping(frontmatter);
+
+    for preprocessor in &options.preprocessors {
+        match preprocessor(path, &frontmatter) {
+            PostprocessorResult::Continue => continue,
+            PostprocessorResult::Stop => break,
+            PostprocessorResult::StopAndSkipNote => return Ok(()),
+        }
+    }
+    let Value::Mapping(frontmatter) = frontmatter else {
+        unreachable!("constructed as a Mapping above")
+    };
+
+    let relative = path.strip_prefix(vault).unwrap_or(path);
+    let mut context = Context::new(path.to_path_buf(), destination.join(relative), frontmatter);
+
+    let content = resolve_embeds(&content, vault, &options.embed_filters);
+    let mut events: MarkdownEvents = Parser::new(&content).collect();
+    for postprocessor in &options.postprocessors {
+        match postprocessor(&mut context, &mut events) {
+            PostprocessorResult::Continue => continue,
+            PostprocessorResult::Stop => break,
+            PostprocessorResult::StopAndSkipNote => return Ok(()),
+        }
+    }
+
+    write_markdown_events(&context.destination, &events)
+}
+
+fn write_markdown_events(destination: &Path, events: &MarkdownEvents<'_>) -> Result<(), ExportError> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).context(WriteSnafu { path: parent.to_path_buf() })?;
+    }
+    let mut buf = String::new();
+    pulldown_cmark_to_cmark::cmark(events.iter().cloned(), &mut buf)
+        .map_err(io::Error::other)
+        .context(WriteSnafu { path: destination.to_path_buf() })?;
+    fs::write(destination, buf).context(WriteSnafu { path: destination.to_path_buf() })
+}
+
+/// Strips `![[target]]`/`![[target|alias]]` embeds whose target note fails any of
+/// `embed_filters`, before the body is tokenized. A target that can't be resolved to a file
+/// in the vault is left untouched, since we have no frontmatter to test it against.
+fn resolve_embeds(content: &str, vault: &Path, embed_filters: &[&EmbedFilter]) -> String {
+    if embed_filters.is_empty() {
+        return content.to_owned();
+    }
+
+    static EMBED: OnceLock<Regex> = OnceLock::new();
+    let embed = EMBED.get_or_init(|| Regex::new(r"!\[\[([^\]|]+)(?:\|[^\]]*)?\]\]").expect("valid regex"));
+
+    embed
+        .replace_all(content, |captures: &regex::Captures| {
+            let target = captures[1].trim();
+            let frontmatter = match resolve_embed_target(vault, target) {
+                Some(path) => match fs::read_to_string(path) {
+                    Ok(raw) => Value::Mapping(read_frontmatter(&raw)),
+                    Err(_) => return captures[0].to_owned(),
+                },
+                None => return captures[0].to_owned(),
+            };
+
+            if embed_filters.iter().all(|filter| filter(&frontmatter)) {
+                captures[0].to_owned()
+            } else {
+                String::new()
+            }
+        })
+        .into_owned()
+}
+
+fn resolve_embed_target(vault: &Path, target: &str) -> Option<PathBuf> {
+    let candidate = vault.join(target);
+    if candidate.is_file() {
+        return Some(candidate);
+    }
+    let with_extension = vault.join(format!("{target}.md"));
+    with_extension.is_file().then_some(with_extension)
+}
+
+fn read_frontmatter(content: &str) -> Mapping {
+    content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---"))
+        .and_then(|(yaml, _)| serde_yaml::from_str(yaml).ok())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_export_vault_applies_preprocessor_and_include_glob() {
+    let vault = std::env::temp_dir().join(format!(
+        "obsidian-export-preparse-test-{}",
+        std::process::id()
+    ));
+    let posts = vault.join("posts");
+    fs::create_dir_all(&posts).unwrap();
+    fs::write(posts.join("keep.md"), "---\ntags: [publish]\n---\nbody\n").unwrap();
+    fs::write(posts.join("skip.md"), "---\ntags: [draft]\n---\nbody\n").unwrap();
+    fs::write(vault.join("other.md"), "not under posts\n").unwrap();
+
+    let destination = std::env::temp_dir().join(format!(
+        "obsidian-export-preparse-test-out-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&destination);
+
+    let preprocessor = preprocessors::filter_by_tags(vec!["draft".into()], vec![]);
+    let options = WalkOptions::new()
+        .with_include_glob("posts/*.md");
+    let mut options = options;
+    options.preprocessors.push(&preprocessor);
+
+    export_vault(&vault, &destination, &options).unwrap();
+
+    assert!(
+        destination.join("posts/keep.md").is_file(),
+        "a note that passes the pre-parse filter and matches the include glob is exported"
+    );
+    assert!(
+        !destination.join("posts/skip.md").exists(),
+        "a note skipped by the pre-parse filter is never tokenized or written"
+    );
+    assert!(
+        !destination.join("other.md").exists(),
+        "a note outside every include glob's base directory is pruned before it's read"
+    );
+
+    fs::remove_dir_all(&vault).unwrap();
+    let _ = fs::remove_dir_all(&destination);
+}
+
+#[test]
+fn test_export_vault_strips_embeds_rejected_by_embed_filter() {
+    let vault = std::env::temp_dir().join(format!(
+        "obsidian-export-embed-test-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&vault).unwrap();
+    fs::write(
+        vault.join("private.md"),
+        "---\ntags: [private]\n---\nsecret\n",
+    )
+    .unwrap();
+    fs::write(
+        vault.join("public.md"),
+        "---\ntags: [public]\n---\nshared\n",
+    )
+    .unwrap();
+    fs::write(
+        vault.join("index.md"),
+        "before ![[private]] ![[public]] after\n",
+    )
+    .unwrap();
+
+    let destination = std::env::temp_dir().join(format!(
+        "obsidian-export-embed-test-out-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&destination);
+
+    let embeddable = postprocessors::filter_embed_by_tags(vec!["private".into()], vec![]);
+    let mut options = WalkOptions::new();
+    options.embed_filters.push(&embeddable);
+
+    export_vault(&vault, &destination, &options).unwrap();
+
+    let exported = fs::read_to_string(destination.join("index.md")).unwrap();
+    assert!(
+        !exported.contains("private"),
+        "an embed of a note rejected by the embed filter is stripped before rendering"
+    );
+    assert!(
+        exported.contains("public"),
+        "an embed of a note the embed filter allows is left untouched"
+    );
+
+    fs::remove_dir_all(&vault).unwrap();
+    let _ = fs::remove_dir_all(&destination);
+}
+
+#[test]
+fn test_walk_dir_honors_deeper_negation() {
+    let vault = std::env::temp_dir().join(format!(
+        "obsidian-export-walk-test-{}",
+        std::process::id()
+    ));
+    let drafts = vault.join("drafts");
+    fs::create_dir_all(&drafts).unwrap();
+    fs::write(vault.join(".export-ignore"), "drafts/\n").unwrap();
+    fs::write(drafts.join(".export-ignore"), "!keep-me.md\n").unwrap();
+    fs::write(drafts.join("keep-me.md"), "kept\n").unwrap();
+    fs::write(drafts.join("other.md"), "dropped\n").unwrap();
+
+    let destination = std::env::temp_dir().join(format!(
+        "obsidian-export-walk-test-out-{}",
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&destination);
+
+    export_vault(&vault, &destination, &WalkOptions::new()).unwrap();
+
+    assert!(
+        destination.join("drafts/keep-me.md").is_file(),
+        "a deeper `!keep-me.md` negation should override the shallower `drafts/` exclusion"
+    );
+    assert!(!destination.join("drafts/other.md").exists());
+
+    fs::remove_dir_all(&vault).unwrap();
+    fs::remove_dir_all(&destination).unwrap();
+}
@@ -2,8 +2,10 @@
 
 use std::{fs, io::ErrorKind, path::Path};
 
+use chrono::NaiveDate;
 use pulldown_cmark::{Event, Tag};
-use serde_yaml::{Value};
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
 use slug::slugify;
 
 use crate::WriteSnafu;
@@ -30,9 +32,12 @@ pub fn destination_from_frontmatter(
     context: &mut Context,
     events: &mut MarkdownEvents<'_>,
 ) -> PostprocessorResult {
-    let date = context.frontmatter.get("date").and_then(|d| d.as_str()).unwrap_or("1970-01-01").to_owned();
-    let title = context.frontmatter.get("title").and_then(|d| d.as_str()).unwrap_or(context.current_file().file_stem().expect("It is a file").to_str().expect("It is a file")).to_owned();
-    let slug = slugify(title);
+    let fallback_title = context
+        .current_file()
+        .file_stem()
+        .expect("It is a file")
+        .to_str()
+        .expect("It is a file");
     match context.frontmatter.get("export_to") {
         Some(Value::String(export_path)) => {
             let mut from = context.current_file().as_path();
@@ -41,9 +46,12 @@ pub fn destination_from_frontmatter(
                 to = to.parent().unwrap_or(to);
                 from = from.parent().unwrap_or(from);
             }
-            let mut target = export_path.replace(":date", &date);
-            target = target.replace(":title", &slug);
-            context.destination = to.join( Path::new(&target)).to_path_buf();
+            let target = render_destination_template(export_path, &context.frontmatter, fallback_title);
+            // A missing field renders as an empty placeholder, which can leave a leading
+            // `/` in the template (e.g. `:date/:title.md` with no `date` set). Path::join
+            // treats a leading `/` as absolute and would otherwise discard `to` entirely.
+            let target = target.trim_start_matches('/');
+            context.destination = to.join(Path::new(target)).to_path_buf();
 
             for event in events.iter_mut() {
                 match event {
@@ -78,6 +86,57 @@ pub fn destination_from_frontmatter(
     PostprocessorResult::Continue
 }
 
+/// Substitutes `:field_name` placeholders (and `:date(%Y/%m)`-style date formats) in an `export_to` template against the note's frontmatter, falling back to `fallback_title` for `:title`.
+fn render_destination_template(template: &str, frontmatter: &Mapping, fallback_title: &str) -> String {
+    static PLACEHOLDER: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let placeholder =
+        PLACEHOLDER.get_or_init(|| Regex::new(r":([A-Za-z_][A-Za-z0-9_]*)(?:\(([^)]*)\))?").expect("valid regex"));
+    placeholder
+        .replace_all(template, |captures: &regex::Captures| {
+            let field = &captures[1];
+            let format = captures.get(2).map(|m| m.as_str());
+            substitute_destination_field(frontmatter, field, format, fallback_title)
+        })
+        .into_owned()
+}
+
+fn substitute_destination_field(
+    frontmatter: &Mapping,
+    field: &str,
+    format: Option<&str>,
+    fallback_title: &str,
+) -> String {
+    if field == "title" {
+        let title = frontmatter
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or(fallback_title);
+        return slugify(title);
+    }
+
+    match frontmatter.get(field) {
+        Some(Value::String(value)) => {
+            if field == "date" {
+                if let Some(format) = format {
+                    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                        return date.format(format).to_string();
+                    }
+                }
+            }
+            slugify(value)
+        }
+        Some(Value::Sequence(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(slugify)
+            .collect::<Vec<_>>()
+            .join("-"),
+        Some(Value::Bool(value)) => value.to_string(),
+        Some(Value::Number(value)) => value.to_string(),
+        _ => String::new(),
+    }
+}
+
 pub fn filter_by_tags(
     skip_tags: Vec<String>,
     only_tags: Vec<String>,
@@ -91,6 +150,22 @@ pub fn filter_by_tags(
     }
 }
 
+/// Like [`filter_by_tags`], but for [`crate::WalkOptions::embed_filters`]: it decides
+/// whether a note may be pulled in through embed/transclusion resolution (`![[other note]]`)
+/// rather than whether it is written out as its own export-output file.
+pub fn filter_embed_by_tags(
+    skip_tags: Vec<String>,
+    only_tags: Vec<String>,
+) -> impl Fn(&Value) -> bool {
+    move |frontmatter: &Value| -> bool {
+        match frontmatter.get("tags") {
+            None => tags_allowed(&[], &skip_tags, &only_tags),
+            Some(Value::Sequence(tags)) => tags_allowed(tags, &skip_tags, &only_tags),
+            _ => true,
+        }
+    }
+}
+
 pub fn remove_specified_tags(
     to_remove: Vec<String>,
 ) -> impl Fn(&mut Context, &mut MarkdownEvents<'_>) -> PostprocessorResult {
@@ -110,23 +185,94 @@ pub fn remove_specified_tags(
     }
 }
 
-fn filter_by_tags_(
+pub(crate) fn filter_by_tags_(
     tags: &[Value],
     skip_tags: &[String],
     only_tags: &[String],
 ) -> PostprocessorResult {
+    if tags_allowed(tags, skip_tags, only_tags) {
+        PostprocessorResult::Continue
+    } else {
+        PostprocessorResult::StopAndSkipNote
+    }
+}
+
+/// Shared skip/include logic behind [`filter_by_tags`] and [`filter_embed_by_tags`], hierarchy-aware so excluding `project` also excludes `project/alpha`.
+fn tags_allowed(tags: &[Value], skip_tags: &[String], only_tags: &[String]) -> bool {
+    let note_tags: Vec<&str> = tags.iter().filter_map(Value::as_str).collect();
+
     let skip = skip_tags
         .iter()
-        .any(|tag| tags.contains(&Value::String(tag.to_string())));
+        .any(|pattern| note_tags.iter().any(|tag| tag_is_or_descends_from(tag, pattern)));
     let include = only_tags.is_empty()
         || only_tags
             .iter()
-            .any(|tag| tags.contains(&Value::String(tag.to_string())));
+            .any(|pattern| note_tags.iter().any(|tag| tag_is_or_descends_from(tag, pattern)));
 
-    if skip || !include {
-        PostprocessorResult::StopAndSkipNote
-    } else {
-        PostprocessorResult::Continue
+    !skip && include
+}
+
+/// Returns true if `tag` is `ancestor` itself or one of its nested children, e.g.
+/// `tag_is_or_descends_from("project/alpha", "project")` is true.
+fn tag_is_or_descends_from(tag: &str, ancestor: &str) -> bool {
+    tag == ancestor || tag.starts_with(&format!("{}/", ancestor))
+}
+
+/// A single skip/include value for [`filter_by_frontmatter`]: either matched literally, or
+/// as a compiled regular expression.
+pub enum FrontmatterValue {
+    Literal(String),
+    Pattern(Regex),
+}
+
+impl FrontmatterValue {
+    fn is_match(&self, candidate: &str) -> bool {
+        match self {
+            FrontmatterValue::Literal(literal) => literal == candidate,
+            FrontmatterValue::Pattern(pattern) => pattern.is_match(candidate),
+        }
+    }
+}
+
+/// A generalisation of [`filter_by_tags`] to any frontmatter field, scalar or sequence, with skip/include values matched literally or as a [`FrontmatterValue::Pattern`].
+pub fn filter_by_frontmatter(
+    field: String,
+    skip_values: Vec<FrontmatterValue>,
+    only_values: Vec<FrontmatterValue>,
+) -> impl Fn(&mut Context, &mut MarkdownEvents<'_>) -> PostprocessorResult {
+    move |context: &mut Context, _events: &mut MarkdownEvents<'_>| -> PostprocessorResult {
+        let candidates: Vec<String> = match context.frontmatter.get(&field) {
+            None => vec![],
+            Some(Value::Sequence(values)) => {
+                values.iter().filter_map(frontmatter_value_to_string).collect()
+            }
+            Some(value) => frontmatter_value_to_string(value).into_iter().collect(),
+        };
+
+        let skip = skip_values
+            .iter()
+            .any(|pattern| candidates.iter().any(|candidate| pattern.is_match(candidate)));
+        let include = only_values.is_empty()
+            || only_values
+                .iter()
+                .any(|pattern| candidates.iter().any(|candidate| pattern.is_match(candidate)));
+
+        if skip || !include {
+            PostprocessorResult::StopAndSkipNote
+        } else {
+            PostprocessorResult::Continue
+        }
+    }
+}
+
+/// Renders a scalar frontmatter value the way a user would write it in YAML, so it can be
+/// compared against a [`FrontmatterValue`].
+fn frontmatter_value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
     }
 }
 
@@ -183,3 +329,106 @@ fn test_filter_tags() {
         "When both inclusion and exclusion tags match exclusion wins"
     );
 }
+
+#[test]
+fn test_filter_embed_by_tags() {
+    let private = Value::Mapping(
+        [(
+            Value::String("tags".into()),
+            Value::Sequence(vec![Value::String("private".into())]),
+        )]
+        .into_iter()
+        .collect(),
+    );
+    let public = Value::Mapping(
+        [(
+            Value::String("tags".into()),
+            Value::Sequence(vec![Value::String("public".into())]),
+        )]
+        .into_iter()
+        .collect(),
+    );
+
+    let embeddable = filter_embed_by_tags(vec!["private".into()], vec![]);
+    assert!(
+        !embeddable(&private),
+        "A note tagged #private may not be embedded"
+    );
+    assert!(
+        embeddable(&public),
+        "A note without a skipped tag may be embedded"
+    );
+}
+
+#[test]
+fn test_nested_tags_are_hierarchy_aware() {
+    let tags = vec![Value::String("project/alpha".into())];
+    assert_eq!(
+        filter_by_tags_(&tags, &["project".into()], &[]),
+        PostprocessorResult::StopAndSkipNote,
+        "Excluding a parent tag also excludes its nested children"
+    );
+    assert_eq!(
+        filter_by_tags_(&tags, &["project/beta".into()], &[]),
+        PostprocessorResult::Continue,
+        "Excluding a sibling tag does not affect unrelated nested children"
+    );
+}
+
+#[test]
+fn test_filter_by_frontmatter_scalar() {
+    let filter = filter_by_frontmatter(
+        "draft".into(),
+        vec![FrontmatterValue::Literal("true".into())],
+        vec![],
+    );
+    let allowed = |yaml: &str| {
+        let frontmatter: Mapping = serde_yaml::from_str(yaml).unwrap();
+        let mut context = Context::new("note.md".into(), "out/note.md".into(), frontmatter);
+        filter(&mut context, &mut vec![]) == PostprocessorResult::Continue
+    };
+    assert!(!allowed("draft: true"), "draft: true should be skipped");
+    assert!(allowed("draft: false"), "draft: false should be kept");
+}
+
+#[test]
+fn test_filter_by_frontmatter_regex() {
+    let pattern = FrontmatterValue::Pattern(Regex::new("^wip").unwrap());
+    assert!(pattern.is_match("wip-notes"));
+    assert!(!pattern.is_match("published"));
+}
+
+#[test]
+fn test_render_destination_template() {
+    let frontmatter: Mapping = serde_yaml::from_str(
+        "title: My Post\ndate: 2023-04-05\ncategory: Rust Tips\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        render_destination_template(":date/:title.md", &frontmatter, "fallback"),
+        "2023-04-05/my-post.md"
+    );
+    assert_eq!(
+        render_destination_template(":date(%Y/%m)/:title.md", &frontmatter, "fallback"),
+        "2023/04/my-post.md"
+    );
+    assert_eq!(
+        render_destination_template(":category/:title.md", &frontmatter, "fallback"),
+        "rust-tips/my-post.md"
+    );
+    assert_eq!(
+        render_destination_template(":missing/:title.md", &frontmatter, "fallback"),
+        "/my-post.md",
+        "a missing field renders empty; callers trim the resulting leading slash before joining"
+    );
+}
+
+#[test]
+fn test_render_destination_template_falls_back_to_filename() {
+    let frontmatter: Mapping = serde_yaml::from_str("date: 2023-04-05\n").unwrap();
+    assert_eq!(
+        render_destination_template(":title.md", &frontmatter, "my-file"),
+        "my-file.md"
+    );
+}
@@ -0,0 +1,49 @@
+//! Hooks that run before a note's body is tokenized into [`crate::MarkdownEvents`].
+//!
+//! [`crate::postprocessors`] decide what happens to a note only after its whole body has
+//! already been parsed, which means a [`crate::PostprocessorResult::StopAndSkipNote`] still
+//! pays for parsing work that gets thrown away. A preprocessor instead receives just the
+//! note's path and its already-parsed frontmatter, and returns the same Continue/Skip
+//! decision before the body is ever tokenized.
+
+use std::path::Path;
+
+use serde_yaml::Value;
+
+use crate::postprocessors::filter_by_tags_;
+use crate::PostprocessorResult;
+
+/// A hook tested against a note's path and frontmatter before its body is parsed.
+pub type Preprocessor = dyn Fn(&Path, &Value) -> PostprocessorResult + Send + Sync;
+
+/// The pre-parse equivalent of [`crate::postprocessors::filter_by_tags`]: skips notes
+/// without ever tokenizing their body, instead of discarding the parsed
+/// [`crate::MarkdownEvents`] after the fact.
+pub fn filter_by_tags(
+    skip_tags: Vec<String>,
+    only_tags: Vec<String>,
+) -> impl Fn(&Path, &Value) -> PostprocessorResult {
+    move |_path: &Path, frontmatter: &Value| -> PostprocessorResult {
+        match frontmatter.get("tags") {
+            None => filter_by_tags_(&[], &skip_tags, &only_tags),
+            Some(Value::Sequence(tags)) => filter_by_tags_(tags, &skip_tags, &only_tags),
+            _ => PostprocessorResult::Continue,
+        }
+    }
+}
+
+#[test]
+fn test_preparse_filter_by_tags() {
+    let frontmatter: Value = serde_yaml::from_str("tags: [draft]").unwrap();
+    let filter = filter_by_tags(vec!["draft".into()], vec![]);
+    assert_eq!(
+        filter(Path::new("note.md"), &frontmatter),
+        PostprocessorResult::StopAndSkipNote
+    );
+
+    let frontmatter: Value = serde_yaml::from_str("tags: [publish]").unwrap();
+    assert_eq!(
+        filter(Path::new("note.md"), &frontmatter),
+        PostprocessorResult::Continue
+    );
+}
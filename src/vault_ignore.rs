@@ -0,0 +1,274 @@
+//! Gitignore-style `.export-ignore`/`.gitignore` exclusion rules for vault traversal.
+
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Directories that are never walked into, regardless of what any ignore file says.
+pub(crate) const ALWAYS_SKIP_DIRS: &[&str] = &[".git", ".obsidian"];
+
+/// Names of files that, when found in a directory, contribute rules to the [`IgnoreStack`],
+/// in priority order: patterns from a later name win over patterns from an earlier one
+/// when both appear in the same directory, so `.export-ignore` overrides `.gitignore`.
+pub(crate) const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".export-ignore"];
+
+/// Returns true if `name` (a single path component, not a full path) should never be
+/// descended into while walking a vault.
+pub(crate) fn is_always_skipped_dir(name: &str) -> bool {
+    ALWAYS_SKIP_DIRS.contains(&name)
+}
+
+/// A single compiled line from an ignore file.
+#[derive(Debug, Clone)]
+struct IgnorePattern {
+    /// True when the line started with `!`, meaning a match un-ignores the path.
+    negated: bool,
+    /// True when the line ended in `/`, meaning it may only match directories.
+    dir_only: bool,
+    /// True when the pattern contains a non-trailing `/`, meaning it's anchored to the
+    /// ignore file's directory rather than matching at any depth below it.
+    anchored: bool,
+    /// The glob pattern itself, with any leading/trailing slashes already stripped.
+    glob: String,
+}
+
+impl IgnorePattern {
+    fn parse(line: &str) -> Option<IgnorePattern> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+
+        let dir_only = line.ends_with('/') && line.len() > 1;
+        let line = line.strip_suffix('/').unwrap_or(line);
+
+        // A slash anywhere except the very end anchors the pattern to the directory the
+        // ignore file lives in, matching git's semantics.
+        let anchored = line.contains('/');
+        let glob = line.trim_start_matches('/').to_owned();
+
+        if glob.is_empty() {
+            return None;
+        }
+
+        Some(IgnorePattern {
+            negated,
+            dir_only,
+            anchored,
+            glob,
+        })
+    }
+
+    /// Tests `relative` (a `/`-separated path relative to the ignore file's directory)
+    /// against this pattern.
+    fn is_match(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only {
+            // A dir-only pattern ignores the directory *and everything under it*, so a
+            // file matches when any of its ancestor directories matches, not just when
+            // the file itself is tested with `is_dir = true`.
+            let components: Vec<&str> = relative.split('/').collect();
+            let ancestor_count = if is_dir {
+                components.len()
+            } else {
+                components.len().saturating_sub(1)
+            };
+            (0..ancestor_count).any(|end| self.matches_path(&components[..=end].join("/")))
+        } else {
+            self.matches_path(relative)
+        }
+    }
+
+    fn matches_path(&self, relative: &str) -> bool {
+        if self.anchored {
+            glob_match(&self.glob, relative)
+        } else {
+            // An unanchored pattern matches against the full relative path or any of its
+            // trailing components, e.g. `foo.md` matches both `foo.md` and `bar/foo.md`.
+            let components: Vec<&str> = relative.split('/').collect();
+            (0..components.len()).any(|start| glob_match(&self.glob, &components[start..].join("/")))
+        }
+    }
+}
+
+/// A single parsed ignore file, anchored to the directory it was found in.
+#[derive(Debug, Clone)]
+struct IgnoreFile {
+    base: PathBuf,
+    patterns: Vec<IgnorePattern>,
+}
+
+/// An accumulating stack of ignore files encountered while walking down into a vault, tested most-specific-last so a deeper directory's rules win over a shallower one's.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IgnoreStack {
+    files: Vec<IgnoreFile>,
+}
+
+impl IgnoreStack {
+    pub(crate) fn new() -> IgnoreStack {
+        IgnoreStack { files: Vec::new() }
+    }
+
+    /// Reads `dir` for every recognised ignore file and, if any are present, pushes their
+    /// combined rules onto the stack. Returns whether anything was pushed, so the caller
+    /// knows whether to pop it again once the directory has been fully walked.
+    pub(crate) fn push_dir(&mut self, dir: &Path) -> io::Result<bool> {
+        let mut patterns = Vec::new();
+        for name in IGNORE_FILE_NAMES {
+            let candidate = dir.join(name);
+            if candidate.is_file() {
+                let contents = fs::read_to_string(&candidate)?;
+                patterns.extend(contents.lines().filter_map(IgnorePattern::parse));
+            }
+        }
+
+        if patterns.is_empty() {
+            return Ok(false);
+        }
+
+        self.files.push(IgnoreFile {
+            base: dir.to_path_buf(),
+            patterns,
+        });
+        Ok(true)
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.files.pop();
+    }
+
+    /// Returns true if `path` should be excluded from the export.
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for file in &self.files {
+            let relative = match path.strip_prefix(&file.base) {
+                Ok(relative) => relative,
+                Err(_) => continue,
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            for pattern in &file.patterns {
+                if pattern.is_match(&relative, is_dir) {
+                    ignored = !pattern.negated;
+                }
+            }
+        }
+        ignored
+    }
+}
+
+/// A tiny `.gitignore`-flavoured glob matcher supporting `*`, `?` and `**`.
+///
+/// This intentionally only implements the subset of glob syntax gitignore files commonly
+/// use; it is not a general-purpose glob engine.
+pub(crate) fn glob_match(pattern: &str, candidate: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    glob_match_inner(&pattern, &candidate)
+}
+
+fn glob_match_inner(pattern: &[char], candidate: &[char]) -> bool {
+    match pattern.first() {
+        None => candidate.is_empty(),
+        Some('*') => {
+            // `**` matches across path separators, a single `*` does not.
+            if pattern.get(1) == Some(&'*') {
+                let rest = &pattern[2..];
+                let rest = rest.strip_prefix(&['/']).unwrap_or(rest);
+                (0..=candidate.len()).any(|i| glob_match_inner(rest, &candidate[i..]))
+            } else {
+                let rest = &pattern[1..];
+                (0..=candidate.len())
+                    .take_while(|&i| i == 0 || candidate[i - 1] != '/')
+                    .any(|i| glob_match_inner(rest, &candidate[i..]))
+            }
+        }
+        Some('?') => {
+            !candidate.is_empty() && candidate[0] != '/' && glob_match_inner(&pattern[1..], &candidate[1..])
+        }
+        Some(&c) => candidate.first() == Some(&c) && glob_match_inner(&pattern[1..], &candidate[1..]),
+    }
+}
+
+/// Splits a path-include glob (e.g. `posts/**/*.md`) into its longest non-glob base directory and the pattern remaining below it, so callers can skip past directories it could never match.
+pub(crate) fn split_include_glob(pattern: &str) -> (PathBuf, String) {
+    let is_glob_component = |component: &str| component.contains(['*', '?', '[']);
+
+    let components: Vec<&str> = pattern.split('/').collect();
+    let base_len = components
+        .iter()
+        .take_while(|component| !is_glob_component(component))
+        .count();
+
+    // Keep at least one component in the relative pattern so callers always have
+    // something to match against, even for a base-only pattern like `posts`.
+    let base_len = base_len.min(components.len().saturating_sub(1));
+
+    let base: PathBuf = components[..base_len].iter().collect();
+    let relative = components[base_len..].join("/");
+
+    (base, relative)
+}
+
+#[test]
+fn test_split_include_glob() {
+    assert_eq!(
+        split_include_glob("posts/**/*.md"),
+        (PathBuf::from("posts"), "**/*.md".to_owned())
+    );
+    assert_eq!(
+        split_include_glob("*.md"),
+        (PathBuf::from(""), "*.md".to_owned())
+    );
+    assert_eq!(
+        split_include_glob("posts/drafts"),
+        (PathBuf::from("posts"), "drafts".to_owned())
+    );
+}
+
+#[test]
+fn test_glob_match_basic() {
+    assert!(glob_match("*.md", "note.md"));
+    assert!(!glob_match("*.md", "note.txt"));
+    assert!(glob_match("draft*", "draft-one"));
+    assert!(!glob_match("*.md", "sub/note.md"));
+    assert!(glob_match("**/*.md", "sub/note.md"));
+    assert!(glob_match("**/*.md", "note.md"));
+    assert!(glob_match("a/**/b", "a/x/y/b"));
+}
+
+#[test]
+fn test_ignore_stack_overrides() {
+    let dir = std::env::temp_dir().join(format!(
+        "obsidian-export-test-{}",
+        std::process::id()
+    ));
+    let nested = dir.join("drafts");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(dir.join(".export-ignore"), "drafts/\n").unwrap();
+    fs::write(nested.join(".export-ignore"), "!keep-me.md\n").unwrap();
+
+    let mut stack = IgnoreStack::new();
+    stack.push_dir(&dir).unwrap();
+    assert!(stack.is_ignored(&nested, true));
+
+    stack.push_dir(&nested).unwrap();
+    assert!(!stack.is_ignored(&nested.join("keep-me.md"), false));
+    assert!(stack.is_ignored(&nested.join("other.md"), false));
+
+    stack.pop();
+    stack.pop();
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_always_skipped_dirs() {
+    assert!(is_always_skipped_dir(".git"));
+    assert!(is_always_skipped_dir(".obsidian"));
+    assert!(!is_always_skipped_dir("notes"));
+}